@@ -0,0 +1,64 @@
+// Copyright 2020 Jared Forth.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A host-scoped store of credentials, so a single [`crate::Client`] can
+//! carry more than one token and each is only ever sent to the host it
+//! was issued for. See [`crate::Client::with_auth_tokens`].
+
+use std::collections::HashMap;
+
+/// A single credential that can be attached to outgoing requests.
+#[derive(Clone, Debug)]
+pub enum AuthCredential {
+    /// Sent as `Authorization: Bearer <token>`
+    Bearer(String),
+    /// Sent as `Authorization: Basic <base64(user:password)>`
+    Basic {
+        /// The username
+        user: String,
+        /// The password
+        password: String
+    },
+}
+
+impl AuthCredential {
+    /// Render this credential as the value of an `Authorization` header
+    pub fn to_header_value(&self) -> String {
+        match self {
+            AuthCredential::Bearer(token) => format!("Bearer {}", token),
+            AuthCredential::Basic { user, password } => {
+                let encoded = base64::encode(format!("{}:{}", user, password));
+                format!("Basic {}", encoded)
+            }
+        }
+    }
+}
+
+/// Maps a host (and optional `host:port`) to the credential that should
+/// be attached to requests sent there.
+#[derive(Clone, Debug, Default)]
+pub struct AuthTokens {
+    tokens: HashMap<String, AuthCredential>,
+}
+
+impl AuthTokens {
+    /// Create an empty token store
+    pub fn new() -> AuthTokens {
+        AuthTokens { tokens: HashMap::new() }
+    }
+    /// Register a credential for a host, e.g. `"api.example.com"` or
+    /// `"api.example.com:8443"`
+    pub fn insert(mut self, host: &str, credential: AuthCredential) -> AuthTokens {
+        self.tokens.insert(host.to_string(), credential);
+        self
+    }
+    /// Look up the credential registered for `host`, if any
+    pub fn get(&self, host: &str) -> Option<&AuthCredential> {
+        self.tokens.get(host)
+    }
+}
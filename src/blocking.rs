@@ -0,0 +1,404 @@
+// Copyright 2020 Jared Forth.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A blocking, synchronous wrapper around [`crate::Client`].
+//!
+//! ## Usage:
+//! ```
+//! use roy::blocking::Client;
+//!
+//! let c = Client::new("https://httpbin.org".to_string());
+//! c.get("/get", false);
+//! ```
+
+use std::time::Duration;
+
+use reqwest::{header, StatusCode};
+use tokio::runtime::{Builder, Runtime};
+
+/// A response whose status, headers, and body were fully read while the
+/// blocking client's runtime was driving it, so they can be inspected with
+/// the runtime no longer running — a raw `reqwest::Response`'s body can
+/// only be read while the runtime that's polling its connection is active.
+pub struct Response {
+    status: StatusCode,
+    headers: header::HeaderMap,
+    body: Vec<u8>,
+}
+
+impl Response {
+    async fn buffer(res: reqwest::Response) -> Option<Response> {
+        let status = res.status();
+        let headers = res.headers().clone();
+        let body = res.bytes().await.ok()?.to_vec();
+        Some(Response { status, headers, body })
+    }
+    /// The response's status code
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+    /// The response's headers
+    pub fn headers(&self) -> &header::HeaderMap {
+        &self.headers
+    }
+    /// The response body
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+    /// The response body, decoded as UTF-8 with invalid sequences replaced
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+    /// Deserialize the response body as JSON
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Option<T> {
+        serde_json::from_slice(&self.body).ok()
+    }
+}
+
+/// A blocking HTTP client that mirrors the async [`crate::Client`] API.
+///
+/// Internally owns a single-threaded `tokio` runtime and blocks on it to
+/// drive each request to completion, so it can be used from code that
+/// isn't already running inside an async executor.
+pub struct Client {
+    /// API base URL
+    pub base_url: String,
+    /// The async client being wrapped
+    inner: crate::Client,
+    /// The runtime used to drive `inner`'s futures to completion
+    runtime: Runtime,
+}
+
+impl Client {
+    /// Create a new instance of a blocking `Client`
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// assert_eq!(c.base_url, "https://httpbin.org");
+    /// ```
+    pub fn new(base_url: String) -> Client {
+        Client {
+            base_url: base_url.clone(),
+            inner: crate::Client::new(base_url),
+            runtime: Client::runtime(),
+        }
+    }
+    /// Create a new instance of an authenticated blocking `Client`
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    ///
+    /// let c = Client::new_auth("https://httpbin.org".to_string(), "".to_string());
+    /// assert_eq!(c.get("/bearer", false).is_some(), true);
+    /// ```
+    pub fn new_auth(base_url: String, auth_token: String) -> Client {
+        Client {
+            base_url: base_url.clone(),
+            inner: crate::Client::new_auth(base_url, auth_token),
+            runtime: Client::runtime(),
+        }
+    }
+    /// Create a new instance of a blocking `Client` backed by an in-memory
+    /// GET response cache. See [`crate::Client::new_cached`].
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    ///
+    /// let c = Client::new_cached("https://httpbin.org".to_string(), 100);
+    /// assert_eq!(c.base_url, "https://httpbin.org");
+    /// ```
+    pub fn new_cached(base_url: String, capacity: usize) -> Client {
+        Client {
+            base_url: base_url.clone(),
+            inner: crate::Client::new_cached(base_url, capacity),
+            runtime: Client::runtime(),
+        }
+    }
+    /// Create a new instance of a blocking `Client` backed by a host-scoped
+    /// auth token store. See [`crate::Client::with_auth_tokens`].
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    /// use roy::auth::{AuthTokens, AuthCredential};
+    ///
+    /// let tokens = AuthTokens::new().insert("httpbin.org", AuthCredential::Bearer("abc123".to_string()));
+    /// let c = Client::with_auth_tokens("https://httpbin.org".to_string(), tokens);
+    /// assert_eq!(c.base_url, "https://httpbin.org");
+    /// ```
+    pub fn with_auth_tokens(base_url: String, auth_tokens: crate::auth::AuthTokens) -> Client {
+        Client {
+            base_url: base_url.clone(),
+            inner: crate::Client::with_auth_tokens(base_url, auth_tokens),
+            runtime: Client::runtime(),
+        }
+    }
+    /// Generic function to POST data to an endpoint
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// assert_eq!(c.post("/post", "{data}").is_some(), true);
+    /// ```
+    pub fn post<T: serde::ser::Serialize + std::fmt::Debug>(&self, endpoint: &str, data: T) -> Option<Response> {
+        self.runtime.block_on(async { Response::buffer(self.inner.post(endpoint, data).await?).await })
+    }
+    /// Generic function to POST data to an endpoint and deserialize the JSON response
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    /// use serde_json::Value;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res: Option<Value> = c.post_json("/post", "{data}");
+    /// assert_eq!(res.is_some(), true);
+    /// ```
+    pub fn post_json<T: serde::ser::Serialize + std::fmt::Debug, R: serde::de::DeserializeOwned>(&self, endpoint: &str, data: T) -> Option<R> {
+        self.runtime.block_on(self.inner.post_json(endpoint, data))
+    }
+    /// Generic function to DELETE to an endpoint
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// assert_eq!(c.delete("/delete").is_some(), true);
+    /// ```
+    pub fn delete(&self, endpoint: &str) -> Option<Response> {
+        self.runtime.block_on(async { Response::buffer(self.inner.delete(endpoint).await?).await })
+    }
+    /// Generic function to PATCH data to an endpoint
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// assert_eq!(c.patch("/patch", "{data}").is_some(), true);
+    /// ```
+    pub fn patch<T: serde::ser::Serialize + std::fmt::Debug>(&self, endpoint: &str, data: T) -> Option<Response> {
+        self.runtime.block_on(async { Response::buffer(self.inner.patch(endpoint, data).await?).await })
+    }
+    /// Generic function to PATCH data to an endpoint and deserialize the JSON response
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    /// use serde_json::Value;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res: Option<Value> = c.patch_json("/patch", "{data}");
+    /// assert_eq!(res.is_some(), true);
+    /// ```
+    pub fn patch_json<T: serde::ser::Serialize + std::fmt::Debug, R: serde::de::DeserializeOwned>(&self, endpoint: &str, data: T) -> Option<R> {
+        self.runtime.block_on(self.inner.patch_json(endpoint, data))
+    }
+    /// Generic function to PUT data to an endpoint
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// assert_eq!(c.put("/put", "{data}").is_some(), true);
+    /// ```
+    pub fn put<T: serde::ser::Serialize + std::fmt::Debug>(&self, endpoint: &str, data: T) -> Option<Response> {
+        self.runtime.block_on(async { Response::buffer(self.inner.put(endpoint, data).await?).await })
+    }
+    /// Generic function to PUT data to an endpoint and deserialize the JSON response
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    /// use serde_json::Value;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res: Option<Value> = c.put_json("/put", "{data}");
+    /// assert_eq!(res.is_some(), true);
+    /// ```
+    pub fn put_json<T: serde::ser::Serialize + std::fmt::Debug, R: serde::de::DeserializeOwned>(&self, endpoint: &str, data: T) -> Option<R> {
+        self.runtime.block_on(self.inner.put_json(endpoint, data))
+    }
+    /// Generic function to send a GET request to an endpoint
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// assert_eq!(c.get("/get", false).is_some(), true);
+    /// ```
+    pub fn get(&self, endpoint: &str, single: bool) -> Option<Response> {
+        self.runtime.block_on(async { Response::buffer(self.inner.get(endpoint, single).await?).await })
+    }
+    /// Generic function to send a GET request to an endpoint and deserialize the JSON response
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    /// use serde_json::Value;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res: Option<Value> = c.get_json("/get", false);
+    /// assert_eq!(res.is_some(), true);
+    /// ```
+    pub fn get_json<T: serde::de::DeserializeOwned>(&self, endpoint: &str, single: bool) -> Option<T> {
+        self.runtime.block_on(self.inner.get_json(endpoint, single))
+    }
+    /// Generic function to send a GET request to an endpoint
+    /// without formating to use the base url.
+    ///
+    /// Regardless of the value of `base_url`, this function
+    /// will send a GET request to the absolute URL passed
+    /// as the `url` parameter.
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    ///
+    /// let c = Client::new("https://doesnotexist.example.io".to_string());
+    /// assert_eq!(c.get_abs("https://httpbin.org", false).is_some(), true);
+    /// ```
+    pub fn get_abs(&self, url: &str, single: bool) -> Option<Response> {
+        self.runtime.block_on(async { Response::buffer(self.inner.get_abs(url, single).await?).await })
+    }
+    /// Format a URL
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// assert_eq!(c.format_url("/test"), "https://httpbin.org/test")
+    /// ```
+    pub fn format_url(&self, endpoint: &str) -> String {
+        self.inner.format_url(endpoint)
+    }
+    /// Make a request to the specified endpoint with a specified request method.
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    /// use roy::RequestMethod;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    ///
+    /// assert!(c.request("/get", RequestMethod::GET, None).is_some());
+    /// assert!(c.request("/post", RequestMethod::POST, Some("{}")).is_some());
+    /// assert!(c.request("/patch", RequestMethod::PATCH, Some("{}")).is_some());
+    /// assert!(c.request("/put", RequestMethod::PUT, Some("{}")).is_some());
+    /// assert!(c.request("/delete", RequestMethod::DELETE, None).is_some());
+    /// ```
+    pub fn request(&self, endpoint: &str, method: crate::RequestMethod, data: Option<&str>) -> Option<Response> {
+        self.runtime.block_on(async { Response::buffer(self.inner.request(endpoint, method, data).await?).await })
+    }
+    /// Send a single request without following redirects, surfacing any
+    /// redirect to the caller instead of transparently chasing it. See
+    /// [`crate::Client::fetch_once`].
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    /// use roy::{RequestMethod, FetchOnceResult};
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res = c.fetch_once("/get", RequestMethod::GET);
+    /// assert!(matches!(res, Some(FetchOnceResult::Code(_))));
+    /// ```
+    pub fn fetch_once(&self, endpoint: &str, method: crate::RequestMethod) -> Option<crate::FetchOnceResult> {
+        self.runtime.block_on(self.inner.fetch_once(endpoint, method))
+    }
+    /// Send a request, following up to `redirect_limit` redirects, bailing
+    /// out with `None` if the limit is exceeded. See
+    /// [`crate::Client::fetch_following`].
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    /// use roy::{RequestMethod, FetchOnceResult};
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res = c.fetch_following("/get", RequestMethod::GET, 5);
+    /// assert!(matches!(res, Some(FetchOnceResult::Code(_))));
+    /// ```
+    pub fn fetch_following(&self, endpoint: &str, method: crate::RequestMethod, redirect_limit: usize) -> Option<crate::FetchOnceResult> {
+        self.runtime.block_on(self.inner.fetch_following(endpoint, method, redirect_limit))
+    }
+    /// Build a single request with a custom header, query parameter, or
+    /// timeout that the fixed-signature methods above don't expose. See
+    /// [`crate::Client::build`].
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::blocking::Client;
+    /// use roy::RequestMethod;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res = c.build(RequestMethod::GET, "/get")
+    ///     .query("page", "1")
+    ///     .header("X-Request-Id", "abc123")
+    ///     .send();
+    /// assert_eq!(res.is_some(), true);
+    /// ```
+    pub fn build(&self, method: crate::RequestMethod, endpoint: &str) -> RequestBuilder<'_> {
+        RequestBuilder {
+            inner: self.inner.build(method, endpoint),
+            runtime: &self.runtime,
+        }
+    }
+
+    fn runtime() -> Runtime {
+        Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to start the blocking client's tokio runtime")
+    }
+}
+
+/// Blocking counterpart to [`crate::builder::RequestBuilder`]. Returned by
+/// [`Client::build`].
+pub struct RequestBuilder<'a> {
+    inner: crate::builder::RequestBuilder<'a>,
+    runtime: &'a Runtime,
+}
+
+impl<'a> RequestBuilder<'a> {
+    /// Attach a header to the request
+    pub fn header(mut self, key: &str, value: &str) -> RequestBuilder<'a> {
+        self.inner = self.inner.header(key, value);
+        self
+    }
+    /// Attach a query parameter to the request
+    pub fn query(mut self, key: &str, value: &str) -> RequestBuilder<'a> {
+        self.inner = self.inner.query(key, value);
+        self
+    }
+    /// Attach a JSON body to the request
+    pub fn json<T: serde::ser::Serialize>(mut self, body: T) -> RequestBuilder<'a> {
+        self.inner = self.inner.json(body);
+        self
+    }
+    /// Set a timeout for the request
+    pub fn timeout(mut self, duration: Duration) -> RequestBuilder<'a> {
+        self.inner = self.inner.timeout(duration);
+        self
+    }
+    /// Assemble and send the request
+    pub fn send(self) -> Option<Response> {
+        self.runtime.block_on(async { Response::buffer(self.inner.send().await?).await })
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright 2020 Jared Forth.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A chainable builder for one-off requests that need a custom header,
+//! query parameter, or timeout that the fixed-signature `Client` methods
+//! don't expose. Returned by [`crate::Client::build`].
+
+use std::time::Duration;
+
+use reqwest::{header, Response};
+
+use crate::{Client, RequestMethod};
+
+/// Builds a single request against a [`Client`], with optional headers,
+/// query parameters, a JSON body, and a timeout.
+///
+/// ## Usage:
+/// ```
+/// use roy::{Client, RequestMethod};
+/// use tokio_test::block_on;
+///
+/// let c = Client::new("https://httpbin.org".to_string());
+/// let res = block_on(
+///     c.build(RequestMethod::GET, "/get")
+///         .query("page", "1")
+///         .header("X-Request-Id", "abc123")
+///         .send()
+/// );
+/// assert_eq!(res.is_some(), true);
+/// ```
+pub struct RequestBuilder<'a> {
+    client: &'a Client,
+    method: RequestMethod,
+    url: String,
+    body: Option<serde_json::Value>,
+    headers: header::HeaderMap,
+    query: Vec<(String, String)>,
+    timeout: Option<Duration>,
+}
+
+impl<'a> RequestBuilder<'a> {
+    pub(crate) fn new(client: &'a Client, method: RequestMethod, url: String) -> RequestBuilder<'a> {
+        RequestBuilder {
+            client,
+            method,
+            url,
+            body: None,
+            headers: header::HeaderMap::new(),
+            query: Vec::new(),
+            timeout: None,
+        }
+    }
+    /// Attach a header to the request
+    pub fn header(mut self, key: &str, value: &str) -> RequestBuilder<'a> {
+        if let (Ok(name), Ok(val)) = (
+            header::HeaderName::from_bytes(key.as_bytes()),
+            header::HeaderValue::from_str(value),
+        ) {
+            self.headers.insert(name, val);
+        }
+        self
+    }
+    /// Attach a query parameter to the request
+    pub fn query(mut self, key: &str, value: &str) -> RequestBuilder<'a> {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+    /// Attach a JSON body to the request
+    pub fn json<T: serde::ser::Serialize>(mut self, body: T) -> RequestBuilder<'a> {
+        self.body = serde_json::to_value(body).ok();
+        self
+    }
+    /// Set a timeout for the request
+    pub fn timeout(mut self, duration: Duration) -> RequestBuilder<'a> {
+        self.timeout = Some(duration);
+        self
+    }
+    /// Assemble and send the request
+    pub async fn send(self) -> Option<Response> {
+        let req = self.client.client
+            .request(self.method.as_reqwest_method(), &self.url);
+        let mut req = self.client.with_auth(req, &self.url);
+        req = req.headers(self.headers).query(&self.query);
+        if let Some(body) = &self.body {
+            req = req.json(body);
+        }
+        if let Some(timeout) = self.timeout {
+            req = req.timeout(timeout);
+        }
+        req.send().await.ok()
+    }
+}
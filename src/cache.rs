@@ -0,0 +1,146 @@
+// Copyright 2020 Jared Forth.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An in-memory HTTP cache for GET requests, keyed by the resolved URL.
+//!
+//! Freshness is computed from the `Cache-Control` response header, and
+//! stale entries with an `ETag`/`Last-Modified` are revalidated with a
+//! conditional request rather than re-fetched from scratch.
+
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A single cached response and the freshness metadata needed to decide
+/// whether it can still be served, or must be revalidated first.
+#[derive(Clone, Debug)]
+pub struct CacheEntry {
+    /// The cached response body
+    pub body: Vec<u8>,
+    /// The cached response's status code
+    pub status: u16,
+    /// The `ETag` header, if the response had one
+    pub etag: Option<String>,
+    /// The `Last-Modified` header, if the response had one
+    pub last_modified: Option<String>,
+    /// When this entry was stored, in seconds since the epoch
+    pub stored_at: u64,
+    /// The `max-age` directive from `Cache-Control`, if present
+    pub max_age: Option<u64>,
+    /// Whether `Cache-Control: no-cache` was set, forcing revalidation
+    pub no_cache: bool,
+    /// Whether `Cache-Control: must-revalidate` was set
+    pub must_revalidate: bool,
+}
+
+impl CacheEntry {
+    /// Whether this entry can be served without revalidating first
+    pub fn is_fresh(&self) -> bool {
+        if self.no_cache {
+            return false;
+        }
+        let max_age = match self.max_age {
+            Some(max_age) => max_age,
+            None => return false,
+        };
+        now().saturating_sub(self.stored_at) < max_age
+    }
+}
+
+/// The subset of `Cache-Control` directives roy understands.
+#[derive(Default)]
+pub struct CacheControl {
+    /// The `max-age` directive, in seconds
+    pub max_age: Option<u64>,
+    /// The `no-store` directive: the response must never be cached
+    pub no_store: bool,
+    /// The `no-cache` directive: the cached response must be revalidated before use
+    pub no_cache: bool,
+    /// The `must-revalidate` directive
+    pub must_revalidate: bool,
+}
+
+impl CacheControl {
+    /// Parse a `Cache-Control` header value
+    pub fn parse(header_value: &str) -> CacheControl {
+        let mut cache_control = CacheControl::default();
+        for directive in header_value.split(',') {
+            let directive = directive.trim();
+            if let Some(value) = directive.strip_prefix("max-age=") {
+                cache_control.max_age = value.trim().parse().ok();
+            } else if directive.eq_ignore_ascii_case("no-store") {
+                cache_control.no_store = true;
+            } else if directive.eq_ignore_ascii_case("no-cache") {
+                cache_control.no_cache = true;
+            } else if directive.eq_ignore_ascii_case("must-revalidate") {
+                cache_control.must_revalidate = true;
+            }
+        }
+        cache_control
+    }
+}
+
+/// A small, fixed-capacity, least-recently-used cache keyed by URL.
+pub struct LruMap {
+    capacity: usize,
+    order: Vec<String>,
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl LruMap {
+    /// Create an empty cache holding at most `capacity` entries
+    pub fn new(capacity: usize) -> LruMap {
+        LruMap {
+            capacity,
+            order: Vec::new(),
+            entries: HashMap::new(),
+        }
+    }
+    /// Look up an entry, marking it as most-recently-used if found
+    pub fn get(&mut self, key: &str) -> Option<CacheEntry> {
+        if self.entries.contains_key(key) {
+            self.touch(key);
+        }
+        self.entries.get(key).cloned()
+    }
+    /// Insert or replace an entry, evicting the least-recently-used entry if needed
+    pub fn insert(&mut self, key: String, entry: CacheEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.contains_key(&key) {
+            self.touch(&key);
+        } else {
+            if self.entries.len() >= self.capacity {
+                self.evict_oldest();
+            }
+            self.order.push(key.clone());
+        }
+        self.entries.insert(key, entry);
+    }
+
+    fn touch(&mut self, key: &str) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            let key = self.order.remove(pos);
+            self.order.push(key);
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        if !self.order.is_empty() {
+            let oldest = self.order.remove(0);
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -17,15 +17,38 @@
 //!
 //! **roy** also has methods that support POST, PUT, PATCH, and DELETE http verbs,
 //! all of which are used in the same way as the `get()` method.
+//!
+//! If you're not already in an async context, use [`blocking::Client`] instead,
+//! which mirrors this API but blocks on each request for you.
+
+pub mod auth;
+pub mod blocking;
+pub mod builder;
+pub mod cache;
+
+use std::sync::{Arc, Mutex};
 
 use reqwest::{Response, header};
 
+use auth::AuthTokens;
+use cache::{CacheControl, CacheEntry, LruMap};
+
 /// The HTTP client
+#[derive(Clone)]
 pub struct Client {
     /// API base URL
     pub base_url: String,
     /// The reqwest wrapper
-    client: reqwest::Client
+    client: reqwest::Client,
+    /// The optional in-memory GET response cache
+    cache: Option<Arc<Mutex<LruMap>>>,
+    /// The optional host-scoped auth token store
+    auth_tokens: Option<AuthTokens>,
+    /// Default headers baked into `client` (e.g. by [`Client::new_auth`]),
+    /// carried over to requests on `base_url`'s host whenever they need a
+    /// differently-configured `reqwest::Client`, such as
+    /// [`Client::fetch_once_at`]'s redirect-less one
+    default_headers: header::HeaderMap
 }
 
 impl Client {
@@ -41,7 +64,10 @@ impl Client {
     pub fn new(base_url: String) -> Client {
         Client {
             base_url,
-            client: reqwest::Client::new()
+            client: reqwest::Client::new(),
+            cache: None,
+            auth_tokens: None,
+            default_headers: header::HeaderMap::new()
         }
     }
     /// Create a new instance of an authenticated `Client`
@@ -67,11 +93,89 @@ impl Client {
         Client {
             base_url,
             client: reqwest::Client::builder()
-                .default_headers(headers)
+                .default_headers(headers.clone())
                 .build()
-                .unwrap()
+                .unwrap(),
+            cache: None,
+            auth_tokens: None,
+            default_headers: headers
+        }
+    }
+    /// Create a new instance of a `Client` backed by an in-memory GET
+    /// response cache, standards-compliant with `Cache-Control` freshness
+    /// and `ETag`/`Last-Modified` revalidation.
+    ///
+    /// `capacity` is the maximum number of cached responses kept at once;
+    /// the least-recently-used entry is evicted once it's exceeded. The
+    /// cache lives behind an `Arc<Mutex<_>>`, so cloning the `Client`
+    /// stays cheap and shares the same cache.
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::Client;
+    ///
+    /// let c = Client::new_cached("https://httpbin.org".to_string(), 100);
+    /// assert_eq!(c.base_url, "https://httpbin.org");
+    /// ```
+    pub fn new_cached(base_url: String, capacity: usize) -> Client {
+        Client {
+            base_url,
+            client: reqwest::Client::new(),
+            cache: Some(Arc::new(Mutex::new(LruMap::new(capacity)))),
+            auth_tokens: None,
+            default_headers: header::HeaderMap::new()
+        }
+    }
+    /// Create a new instance of a `Client` backed by a host-scoped
+    /// [`AuthTokens`] store, so requests only ever carry a credential to
+    /// the host it was registered for — critically, a token is dropped
+    /// when [`Client::get_abs`] or a redirect sends the request to a
+    /// different host.
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::Client;
+    /// use roy::auth::{AuthTokens, AuthCredential};
+    ///
+    /// let tokens = AuthTokens::new().insert("httpbin.org", AuthCredential::Bearer("abc123".to_string()));
+    /// let c = Client::with_auth_tokens("https://httpbin.org".to_string(), tokens);
+    /// assert_eq!(c.base_url, "https://httpbin.org");
+    /// ```
+    pub fn with_auth_tokens(base_url: String, auth_tokens: AuthTokens) -> Client {
+        Client {
+            base_url,
+            client: reqwest::Client::new(),
+            cache: None,
+            auth_tokens: Some(auth_tokens),
+            default_headers: header::HeaderMap::new()
+        }
+    }
+    /// Look up the `Authorization` header value to send for a request to `url`,
+    /// if a credential is registered for its host
+    fn auth_header_for(&self, url: &str) -> Option<String> {
+        let tokens = self.auth_tokens.as_ref()?;
+        let parsed = reqwest::Url::parse(url).ok()?;
+        let host = parsed.host_str()?;
+        let credential = match parsed.port() {
+            Some(port) => tokens.get(&format!("{}:{}", host, port)).or_else(|| tokens.get(host)),
+            None => tokens.get(host)
+        }?;
+        Some(credential.to_header_value())
+    }
+    /// Attach the `Authorization` header for `url`'s host to `req`, if a
+    /// credential is registered for it
+    fn with_auth(&self, req: reqwest::RequestBuilder, url: &str) -> reqwest::RequestBuilder {
+        match self.auth_header_for(url) {
+            Some(auth) => req.header(header::AUTHORIZATION, auth),
+            None => req
         }
     }
+    /// Whether `url`'s host matches `base_url`'s host
+    fn same_host_as_base_url(&self, url: &str) -> bool {
+        let base_host = reqwest::Url::parse(&self.base_url).ok().and_then(|u| u.host_str().map(String::from));
+        let host = reqwest::Url::parse(url).ok().and_then(|u| u.host_str().map(String::from));
+        base_host.is_some() && base_host == host
+    }
     /// Generic function to POST data to an endpoint
     ///
     /// ## Usage:
@@ -83,12 +187,34 @@ impl Client {
     /// assert_eq!(block_on(c.post("/post", "{data}")).is_some(), true);
     /// ```
     pub async fn post<T: serde::ser::Serialize + std::fmt::Debug>(&self, endpoint: &str, data: T) -> Option<Response> {
-        let res = self.client.post(&self.format_url(endpoint))
-            .json(&data)
-            .send()
-            .await.ok()?;
+        let url = self.format_url(endpoint);
+        let req = self.client.post(&url).json(&data);
+        let req = self.with_auth(req, &url);
+        let res = req.send().await.ok()?;
         Some(res)
     }
+    /// Generic function to POST data to an endpoint and deserialize the JSON response
+    ///
+    /// Returns `None` on a transport error, a non-success status code, or a
+    /// deserialization failure.
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::Client;
+    /// use tokio_test::block_on;
+    /// use serde_json::Value;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res: Option<Value> = block_on(c.post_json("/post", "{data}"));
+    /// assert_eq!(res.is_some(), true);
+    /// ```
+    pub async fn post_json<T: serde::ser::Serialize + std::fmt::Debug, R: serde::de::DeserializeOwned>(&self, endpoint: &str, data: T) -> Option<R> {
+        let res = self.post(endpoint, data).await?;
+        if !res.status().is_success() {
+            return None;
+        }
+        res.json::<R>().await.ok()
+    }
     /// Generic function to DELETE to an endpoint
     ///
     /// ## Usage:
@@ -100,9 +226,10 @@ impl Client {
     /// assert_eq!(block_on(c.delete("/delete")).is_some(), true);
     /// ```
     pub async fn delete(&self, endpoint: &str) -> Option<Response> {
-        let res = self.client.delete(&self.format_url(endpoint))
-            .send()
-            .await.ok()?;
+        let url = self.format_url(endpoint);
+        let req = self.client.delete(&url);
+        let req = self.with_auth(req, &url);
+        let res = req.send().await.ok()?;
         Some(res)
     }
     /// Generic function to PATCH data to an endpoint
@@ -116,12 +243,34 @@ impl Client {
     /// assert_eq!(block_on(c.patch("/patch", "{data}")).is_some(), true);
     /// ```
     pub async fn patch<T: serde::ser::Serialize + std::fmt::Debug>(&self, endpoint: &str, data: T) -> Option<Response> {
-        let res = self.client.patch(&self.format_url(endpoint))
-            .json(&data)
-            .send()
-            .await.ok()?;
+        let url = self.format_url(endpoint);
+        let req = self.client.patch(&url).json(&data);
+        let req = self.with_auth(req, &url);
+        let res = req.send().await.ok()?;
         Some(res)
     }
+    /// Generic function to PATCH data to an endpoint and deserialize the JSON response
+    ///
+    /// Returns `None` on a transport error, a non-success status code, or a
+    /// deserialization failure.
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::Client;
+    /// use tokio_test::block_on;
+    /// use serde_json::Value;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res: Option<Value> = block_on(c.patch_json("/patch", "{data}"));
+    /// assert_eq!(res.is_some(), true);
+    /// ```
+    pub async fn patch_json<T: serde::ser::Serialize + std::fmt::Debug, R: serde::de::DeserializeOwned>(&self, endpoint: &str, data: T) -> Option<R> {
+        let res = self.patch(endpoint, data).await?;
+        if !res.status().is_success() {
+            return None;
+        }
+        res.json::<R>().await.ok()
+    }
     /// Generic function to PUT data to an endpoint
     ///
     /// ## Usage:
@@ -133,12 +282,34 @@ impl Client {
     /// assert_eq!(block_on(c.put("/put", "{data}")).is_some(), true);
     /// ```
     pub async fn put<T: serde::ser::Serialize + std::fmt::Debug>(&self, endpoint: &str, data: T) -> Option<Response> {
-        let res = self.client.put(&self.format_url(endpoint))
-            .json(&data)
-            .send()
-            .await.ok()?;
+        let url = self.format_url(endpoint);
+        let req = self.client.put(&url).json(&data);
+        let req = self.with_auth(req, &url);
+        let res = req.send().await.ok()?;
         Some(res)
     }
+    /// Generic function to PUT data to an endpoint and deserialize the JSON response
+    ///
+    /// Returns `None` on a transport error, a non-success status code, or a
+    /// deserialization failure.
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::Client;
+    /// use tokio_test::block_on;
+    /// use serde_json::Value;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res: Option<Value> = block_on(c.put_json("/put", "{data}"));
+    /// assert_eq!(res.is_some(), true);
+    /// ```
+    pub async fn put_json<T: serde::ser::Serialize + std::fmt::Debug, R: serde::de::DeserializeOwned>(&self, endpoint: &str, data: T) -> Option<R> {
+        let res = self.put(endpoint, data).await?;
+        if !res.status().is_success() {
+            return None;
+        }
+        res.json::<R>().await.ok()
+    }
     /// Generic function to send a GET request to an endpoint
     ///
     /// ## Usage:
@@ -150,19 +321,139 @@ impl Client {
     /// assert_eq!(block_on(c.get("/get", false)).is_some(), true);
     /// ```
     pub async fn get(&self, endpoint: &str, single: bool) -> Option<Response> {
-        let res;
+        if let Some(cache) = self.cache.clone() {
+            return self.get_cached(cache, endpoint, single).await;
+        }
+        let url = self.format_url(endpoint);
+        let mut req = self.client.get(&url);
         if single {
-            res = self.client.get(&self.format_url(endpoint))
-                .header("Accept", "application/vnd.pgrst.object+json")
-                .send()
-                .await.ok()?;
-        } else {
-            res = self.client.get(&self.format_url(endpoint))
-                .send()
-                .await.ok()?;
+            req = req.header("Accept", "application/vnd.pgrst.object+json");
         }
+        let req = self.with_auth(req, &url);
+        let res = req.send().await.ok()?;
         Some(res)
     }
+    /// Serve a GET request from the cache when fresh, revalidating with
+    /// `If-None-Match`/`If-Modified-Since` when stale, and falling back to
+    /// a plain fetch when the response isn't cacheable.
+    async fn get_cached(&self, cache: Arc<Mutex<LruMap>>, endpoint: &str, single: bool) -> Option<Response> {
+        let url = self.format_url(endpoint);
+        let cache_key = format!("{}|{}", url, single);
+        let existing = cache.lock().unwrap().get(&cache_key);
+
+        if let Some(entry) = &existing {
+            if entry.is_fresh() {
+                return Some(Client::response_from_entry(entry));
+            }
+        }
+
+        let mut req = self.client.get(&url);
+        if single {
+            req = req.header("Accept", "application/vnd.pgrst.object+json");
+        }
+        if let Some(entry) = &existing {
+            if let Some(etag) = &entry.etag {
+                req = req.header(header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                req = req.header(header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let req = self.with_auth(req, &url);
+        let res = match req.send().await {
+            Ok(res) => res,
+            // RFC 7234 forbids serving a stale response without successfully
+            // revalidating it when must-revalidate or no-cache was set;
+            // otherwise a stale entry is better than nothing on a failed
+            // revalidation.
+            Err(_) => {
+                return match &existing {
+                    Some(entry) if !entry.must_revalidate && !entry.no_cache => Some(Client::response_from_entry(entry)),
+                    _ => None,
+                };
+            }
+        };
+
+        if res.status() == reqwest::StatusCode::NOT_MODIFIED {
+            let mut entry = existing?;
+            entry.stored_at = cache::now();
+            // RFC 7234 allows a 304 to omit Cache-Control, in which case the
+            // prior freshness metadata stands rather than being cleared.
+            if res.headers().get(header::CACHE_CONTROL).is_some() {
+                let cache_control = Client::parse_cache_control(res.headers());
+                entry.max_age = cache_control.max_age;
+                entry.no_cache = cache_control.no_cache;
+                entry.must_revalidate = cache_control.must_revalidate;
+            }
+            let refreshed = Client::response_from_entry(&entry);
+            cache.lock().unwrap().insert(cache_key, entry);
+            return Some(refreshed);
+        }
+
+        let cache_control = Client::parse_cache_control(res.headers());
+        let status = res.status().as_u16();
+        let etag = res.headers().get(header::ETAG).and_then(|v| v.to_str().ok()).map(String::from);
+        let last_modified = res.headers().get(header::LAST_MODIFIED).and_then(|v| v.to_str().ok()).map(String::from);
+        let body = res.bytes().await.ok()?.to_vec();
+
+        let entry = CacheEntry {
+            body,
+            status,
+            etag,
+            last_modified,
+            stored_at: cache::now(),
+            max_age: cache_control.max_age,
+            no_cache: cache_control.no_cache,
+            must_revalidate: cache_control.must_revalidate
+        };
+        let response = Client::response_from_entry(&entry);
+
+        if !cache_control.no_store {
+            cache.lock().unwrap().insert(cache_key, entry);
+        }
+        Some(response)
+    }
+
+    fn parse_cache_control(headers: &header::HeaderMap) -> CacheControl {
+        headers.get(header::CACHE_CONTROL)
+            .and_then(|v| v.to_str().ok())
+            .map(CacheControl::parse)
+            .unwrap_or_default()
+    }
+
+    fn response_from_entry(entry: &CacheEntry) -> Response {
+        let mut builder = http::Response::builder().status(entry.status);
+        if let Some(etag) = &entry.etag {
+            builder = builder.header(header::ETAG, etag);
+        }
+        if let Some(last_modified) = &entry.last_modified {
+            builder = builder.header(header::LAST_MODIFIED, last_modified);
+        }
+        let http_response = builder.body(entry.body.clone()).expect("cached response is always valid");
+        Response::from(http_response)
+    }
+    /// Generic function to send a GET request to an endpoint and deserialize the JSON response
+    ///
+    /// Returns `None` on a transport error, a non-success status code, or a
+    /// deserialization failure.
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::Client;
+    /// use tokio_test::block_on;
+    /// use serde_json::Value;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res: Option<Value> = block_on(c.get_json("/get", false));
+    /// assert_eq!(res.is_some(), true);
+    /// ```
+    pub async fn get_json<T: serde::de::DeserializeOwned>(&self, endpoint: &str, single: bool) -> Option<T> {
+        let res = self.get(endpoint, single).await?;
+        if !res.status().is_success() {
+            return None;
+        }
+        res.json::<T>().await.ok()
+    }
     /// Generic function to send a GET request to an endpoint
     /// without formating to use the base url.
     ///
@@ -179,17 +470,12 @@ impl Client {
     /// assert_eq!(block_on(c.get_abs("https://httpbin.org", false)).is_some(), true);
     /// ```
     pub async fn get_abs(&self, url: &str, single: bool) -> Option<Response> {
-        let res;
+        let mut req = self.client.get(url);
         if single {
-            res = self.client.get(url)
-                .header("Accept", "application/vnd.pgrst.object+json")
-                .send()
-                .await.ok()?;
-        } else {
-            res = self.client.get(url)
-                .send()
-                .await.ok()?;
+            req = req.header("Accept", "application/vnd.pgrst.object+json");
         }
+        let req = self.with_auth(req, url);
+        let res = req.send().await.ok()?;
         Some(res)
     }
     /// Format a URL
@@ -243,6 +529,100 @@ impl Client {
             }
         }
     }
+    /// Start building a request with custom headers, query parameters, a body, or a timeout.
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::{Client, RequestMethod};
+    /// use tokio_test::block_on;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res = block_on(
+    ///     c.build(RequestMethod::GET, "/get")
+    ///         .query("page", "1")
+    ///         .header("X-Request-Id", "abc123")
+    ///         .send()
+    /// );
+    /// assert_eq!(res.is_some(), true);
+    /// ```
+    pub fn build(&self, method: RequestMethod, endpoint: &str) -> builder::RequestBuilder<'_> {
+        builder::RequestBuilder::new(self, method, self.format_url(endpoint))
+    }
+    /// Send a single request without following redirects, surfacing any
+    /// redirect to the caller instead of transparently chasing it.
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::{Client, RequestMethod, FetchOnceResult};
+    /// use tokio_test::block_on;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res = block_on(c.fetch_once("/get", RequestMethod::GET));
+    /// assert!(matches!(res, Some(FetchOnceResult::Code(_))));
+    /// ```
+    pub async fn fetch_once(&self, endpoint: &str, method: RequestMethod) -> Option<FetchOnceResult> {
+        self.fetch_once_at(&self.format_url(endpoint), method).await
+    }
+    /// Send a request, following up to `redirect_limit` redirects, bailing
+    /// out with `None` if the limit is exceeded.
+    ///
+    /// ## Usage:
+    /// ```
+    /// use roy::{Client, RequestMethod, FetchOnceResult};
+    /// use tokio_test::block_on;
+    ///
+    /// let c = Client::new("https://httpbin.org".to_string());
+    /// let res = block_on(c.fetch_following("/get", RequestMethod::GET, 5));
+    /// assert!(matches!(res, Some(FetchOnceResult::Code(_))));
+    /// ```
+    pub async fn fetch_following(&self, endpoint: &str, method: RequestMethod, redirect_limit: usize) -> Option<FetchOnceResult> {
+        let mut url = self.format_url(endpoint);
+        let mut remaining = redirect_limit;
+        loop {
+            match self.fetch_once_at(&url, method).await? {
+                FetchOnceResult::Redirect(resolved) => {
+                    if remaining == 0 {
+                        return None;
+                    }
+                    remaining -= 1;
+                    url = resolved;
+                }
+                code => return Some(code),
+            }
+        }
+    }
+    /// Send a single request to an already-resolved, absolute `url`
+    /// without following redirects.
+    async fn fetch_once_at(&self, url: &str, method: RequestMethod) -> Option<FetchOnceResult> {
+        let mut builder = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none());
+        // default_headers (e.g. new_auth's baked-in Authorization) were
+        // issued for base_url's host; only carry them to a hop that's still
+        // on that same host, so a redirect elsewhere doesn't leak them.
+        if self.same_host_as_base_url(url) {
+            builder = builder.default_headers(self.default_headers.clone());
+        }
+        let client = builder.build().ok()?;
+        let req = client.request(method.as_reqwest_method(), url);
+        let req = self.with_auth(req, url);
+        let res = req.send().await.ok()?;
+        if res.status().is_redirection() {
+            let location = res.headers().get(header::LOCATION)?.to_str().ok()?;
+            let resolved = reqwest::Url::parse(url).ok()?.join(location).ok()?;
+            Some(FetchOnceResult::Redirect(resolved.to_string()))
+        } else {
+            Some(FetchOnceResult::Code(res))
+        }
+    }
+}
+
+/// The result of a single, non-redirect-following fetch attempt.
+///
+/// Returned by [`Client::fetch_once`] and [`Client::fetch_following`].
+pub enum FetchOnceResult {
+    /// The request completed and returned this final, non-redirect response.
+    Code(Response),
+    /// The server responded with a redirect to this resolved, absolute URL.
+    Redirect(String),
 }
 
 /// Lists the possible HTTP request methods that can be used.
@@ -252,6 +632,7 @@ impl Client {
 /// HTTP defines a set of request methods to indicate the desired action to be performed for a given resource.
 /// Although they can also be nouns, these request methods are sometimes referred to as HTTP verbs.
 ///
+#[derive(Clone, Copy, Debug)]
 pub enum RequestMethod {
     /// The GET method requests a representation of the specified resource. Requests using GET should only retrieve data.
     GET,
@@ -263,4 +644,69 @@ pub enum RequestMethod {
     PATCH,
     /// The DELETE method deletes the specified resource.
     DELETE
+}
+
+impl RequestMethod {
+    /// Convert to the equivalent `reqwest::Method`
+    fn as_reqwest_method(&self) -> reqwest::Method {
+        match self {
+            RequestMethod::GET => reqwest::Method::GET,
+            RequestMethod::POST => reqwest::Method::POST,
+            RequestMethod::PUT => reqwest::Method::PUT,
+            RequestMethod::PATCH => reqwest::Method::PATCH,
+            RequestMethod::DELETE => reqwest::Method::DELETE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use auth::AuthCredential;
+
+    fn client() -> Client {
+        let tokens = AuthTokens::new()
+            .insert("api.example.com", AuthCredential::Bearer("abc123".to_string()))
+            .insert("api.example.com:8443", AuthCredential::Bearer("port-specific".to_string()));
+        Client::with_auth_tokens("https://api.example.com".to_string(), tokens)
+    }
+
+    #[test]
+    fn attaches_the_token_for_the_same_host() {
+        let c = client();
+        assert_eq!(c.auth_header_for("https://api.example.com/get"), Some("Bearer abc123".to_string()));
+    }
+
+    #[test]
+    fn drops_the_token_for_a_different_host() {
+        let c = client();
+        assert_eq!(c.auth_header_for("https://evil.example.com/get"), None);
+    }
+
+    #[test]
+    fn prefers_a_port_specific_token_over_the_bare_host() {
+        let c = client();
+        assert_eq!(
+            c.auth_header_for("https://api.example.com:8443/get"),
+            Some("Bearer port-specific".to_string())
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_bare_host_token_for_an_unregistered_port() {
+        let c = client();
+        assert_eq!(c.auth_header_for("https://api.example.com:9999/get"), Some("Bearer abc123".to_string()));
+    }
+
+    #[test]
+    fn has_no_token_without_an_auth_tokens_store() {
+        let c = Client::new("https://api.example.com".to_string());
+        assert_eq!(c.auth_header_for("https://api.example.com/get"), None);
+    }
+
+    #[test]
+    fn basic_credential_renders_as_base64_user_password() {
+        let credential = AuthCredential::Basic { user: "alice".to_string(), password: "s3cr3t".to_string() };
+        assert_eq!(credential.to_header_value(), "Basic YWxpY2U6czNjcjN0");
+    }
 }
\ No newline at end of file